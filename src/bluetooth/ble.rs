@@ -0,0 +1,106 @@
+//! BLE device and GATT service/characteristic discovery via the WinRT
+//! `Windows::Devices::Bluetooth` APIs, for LE-only headsets the classic
+//! Win32 `BluetoothXxx` functions never surface.
+
+use windows::Devices::Bluetooth::GenericAttributeProfile::GattDeviceService;
+use windows::Devices::Bluetooth::{BluetoothConnectionStatus, BluetoothLEDevice};
+use windows::Devices::Enumeration::DeviceInformation;
+
+use super::device::{Address, Characteristic, Device, Service};
+use super::error::Error;
+use super::Result;
+
+/// Enumerates every Bluetooth LE device Windows currently knows about,
+/// using the WinRT device-selector/`DeviceInformation` pattern rather than
+/// the classic Win32 `BluetoothFindFirstDevice` inquiry, which never sees
+/// BLE-only peripherals.
+pub fn discover_devices() -> Result<Vec<Device>> {
+    let selector = BluetoothLEDevice::GetDeviceSelector().map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+    let device_informations = DeviceInformation::FindAllAsyncAqsFilter(&selector)
+        .and_then(|operation| operation.get())
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+    device_informations
+        .into_iter()
+        .map(|info| {
+            let id = info.Id().map_err(|_| Error::LowEnergyDeviceNotFound)?;
+            let ble_device = BluetoothLEDevice::FromIdAsync(&id)
+                .and_then(|operation| operation.get())
+                .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+            device_from_ble(&ble_device)
+        })
+        .collect()
+}
+
+/// Converts a [BluetoothLEDevice] handle into our unified [Device] shape.
+fn device_from_ble(ble_device: &BluetoothLEDevice) -> Result<Device> {
+    let address = ble_device
+        .BluetoothAddress()
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+    let name = ble_device
+        .Name()
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?
+        .to_string();
+    let connected = ble_device
+        .ConnectionStatus()
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?
+        == BluetoothConnectionStatus::Connected;
+
+    Ok(Device::low_energy(Address::from_u64(address), name, connected))
+}
+
+/// Looks up a BLE device by address and lists the GATT services (and their
+/// characteristics) it advertises, returning them as [Service]s so callers
+/// get one consistent view regardless of transport.
+pub fn get_gatt_services(address: &Address) -> Result<Vec<Service>> {
+    let device = BluetoothLEDevice::FromBluetoothAddressAsync(address.as_u64())
+        .and_then(|operation| operation.get())
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+    let services = device
+        .GetGattServicesAsync()
+        .and_then(|operation| operation.get())
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?
+        .Services()
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+    services
+        .into_iter()
+        .map(|service| {
+            let guid = service.Uuid().map_err(|_| Error::LowEnergyDeviceNotFound)?;
+            let characteristics = get_gatt_characteristics(&service)?;
+
+            Ok(Service {
+                name: super::device::uuid_name(&guid),
+                guid,
+                characteristics,
+            })
+        })
+        .collect()
+}
+
+/// Lists the characteristics a single GATT service exposes.
+fn get_gatt_characteristics(service: &GattDeviceService) -> Result<Vec<Characteristic>> {
+    let characteristics = service
+        .GetCharacteristicsAsync()
+        .and_then(|operation| operation.get())
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?
+        .Characteristics()
+        .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+    characteristics
+        .into_iter()
+        .map(|characteristic| {
+            let guid = characteristic
+                .Uuid()
+                .map_err(|_| Error::LowEnergyDeviceNotFound)?;
+
+            Ok(Characteristic {
+                name: super::device::uuid_name(&guid),
+                guid,
+            })
+        })
+        .collect()
+}
@@ -8,18 +8,23 @@ use std::{
 };
 
 use windows::{
-    core::GUID,
+    core::{GUID, PWSTR},
     Win32::{
         Devices::Bluetooth::{
+            BluetoothAuthenticateDevice, BluetoothAuthenticateDeviceEx,
             BluetoothEnumerateInstalledServices, BluetoothFindDeviceClose,
             BluetoothFindFirstDevice, BluetoothFindNextDevice, BluetoothSetServiceState,
-            BLUETOOTH_ADDRESS, BLUETOOTH_DEVICE_INFO, BLUETOOTH_DEVICE_SEARCH_PARAMS,
+            BLUETOOTH_ADDRESS, BLUETOOTH_AUTHENTICATION_REQUIREMENTS, BLUETOOTH_DEVICE_INFO,
+            BLUETOOTH_DEVICE_SEARCH_PARAMS, BLUETOOTH_OOB_DATA_INFO, BLUETOOTH_SERVICE_ENABLE,
+        },
+        Foundation::{
+            BOOL, ERROR_CANCELLED, ERROR_MORE_DATA, ERROR_TIMEOUT, HANDLE, HWND, WAIT_TIMEOUT,
+            WIN32_ERROR,
         },
-        Foundation::{BOOL, HANDLE},
     },
 };
 
-use super::{error::Error, radio, util, Result, Time};
+use super::{ble, error::Error, radio, util, Result, Time};
 
 /// Wraps the device info struct from the Win32 API for future calls to the Windows API.
 struct BluetoothDeviceInfo(BLUETOOTH_DEVICE_INFO);
@@ -47,18 +52,55 @@ impl Debug for BluetoothDeviceInfo {
 /// to this list as I see fit.
 pub enum DeviceClass {
     Headset,
+    HandsFree,
     Microphone,
     Speaker,
     Headphones,
+    PortableAudio,
     Other,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The Bluetooth transport a device communicates over.
+pub enum Transport {
+    /// Bluetooth BR/EDR, a.k.a. "Classic" Bluetooth.
+    Classic,
+    /// Bluetooth Low Energy.
+    LowEnergy,
+    /// A dual-mode device that supports both BR/EDR and LE.
+    Dual,
+}
+
+#[derive(Debug, Clone)]
+/// A Bluetooth service advertised by a device. Unifies classic SDP service
+/// records and BLE GATT services behind one shape, so callers get one
+/// consistent view regardless of [Transport].
+pub struct Service {
+    pub guid: GUID,
+    pub name: String,
+    /// The GATT characteristics this service exposes. Always empty for
+    /// services discovered through the classic SDP database, which has no
+    /// equivalent concept.
+    pub characteristics: Vec<Characteristic>,
+}
+
+#[derive(Debug, Clone)]
+/// A GATT characteristic exposed by a BLE [Service].
+pub struct Characteristic {
+    pub guid: GUID,
+    pub name: String,
+}
+
 #[derive(Debug)]
 /// Represents an active Bluetooth device on the system. This data comes from
 /// the Windows API
 /// ([windows::Win32::Devices::Bluetooth::BLUETOOTH_DEVICE_INFO]).
 pub struct Device {
     pub class: DeviceClass,
+    /// Whether the device advertises the Audio major service class (bit 21
+    /// of the Class of Device's Major Service Class bitmask).
+    pub has_audio_service: bool,
+    pub transport: Transport,
     pub address: Address,
     pub connected: bool,
     pub remembered: bool,
@@ -66,7 +108,11 @@ pub struct Device {
     pub name: String,
     pub last_seen: Time,
     pub last_connected: Time,
-    device_info: BluetoothDeviceInfo,
+    /// Backing classic device info, used for classic-only operations like
+    /// [Device::pair] and [Device::set_service_state]. `None` for devices
+    /// that were only ever discovered over BLE (see [Device::low_energy]),
+    /// which have no `BLUETOOTH_DEVICE_INFO` to back them.
+    device_info: Option<BluetoothDeviceInfo>,
 }
 
 pub enum Mode {
@@ -74,32 +120,224 @@ pub enum Mode {
     Disable,
 }
 
+/// Service class UUID for the Advanced Audio Distribution Profile (A2DP)
+/// sink role, i.e. the audio-source-to-speaker/headphones direction.
+pub const SERVICE_CLASS_A2DP_SINK: &str = "0000110B-0000-1000-8000-00805F9B34FB";
+
+/// Service class UUID for the Hands-Free Profile (HFP), i.e. the
+/// microphone/call-audio direction used by headsets.
+pub const SERVICE_CLASS_HANDS_FREE: &str = "0000111E-0000-1000-8000-00805F9B34FB";
+
+/// The Bluetooth Base UUID that 16-bit "short form" service UUIDs are
+/// expanded against, as defined by the Bluetooth assigned numbers spec.
+const BLUETOOTH_BASE_UUID_SUFFIX: &str = "0000-1000-8000-00805F9B34FB";
+
+/// Describes how a device should be authenticated in [Device::pair].
+pub enum PairMethod<'a> {
+    /// Legacy PIN or passkey pairing, used by devices without Secure Simple
+    /// Pairing support.
+    Passkey(&'a str),
+    /// Numeric comparison or "Just Works" pairing, where no passkey is
+    /// exchanged and the user instead confirms (or nothing is confirmed at
+    /// all) on both devices.
+    NumericComparison(BLUETOOTH_AUTHENTICATION_REQUIREMENTS),
+    /// Out-of-band pairing, using data obtained via another channel (e.g.
+    /// NFC) ahead of time.
+    OutOfBand(
+        BLUETOOTH_OOB_DATA_INFO,
+        BLUETOOTH_AUTHENTICATION_REQUIREMENTS,
+    ),
+}
+
 impl Device {
-    /// Enables or disables a specific service (identified by a GUID)
-    pub fn set_service_state(&self, service_guid: &str, mode: Mode) {
-        // BluetoothSetServiceState(, pbtdi, pguidservice, dwserviceflags)
+    /// Builds a [Device] for an LE-only device discovered through the WinRT
+    /// Bluetooth APIs (see [super::ble::discover_devices]), which has no
+    /// classic `BLUETOOTH_DEVICE_INFO` backing it. Classic-only operations
+    /// ([Device::pair], [Device::set_service_state]) fail with
+    /// [Error::NotAClassicDevice] on a device built this way.
+    ///
+    /// WinRT does not expose a Class of Device, or last-seen/last-connected
+    /// timestamps, for LE devices the way the classic API does, so `class`
+    /// defaults to [DeviceClass::Other] and the timestamps are set to the
+    /// current time.
+    pub(super) fn low_energy(address: Address, name: String, connected: bool) -> Device {
+        let now = chrono::Local::now().naive_local();
+        Device {
+            class: DeviceClass::Other,
+            has_audio_service: false,
+            transport: Transport::LowEnergy,
+            address,
+            connected,
+            remembered: false,
+            authenticated: false,
+            name,
+            last_seen: Time(now),
+            last_connected: Time(now),
+            device_info: None,
+        }
+    }
+
+    /// Returns the classic `BLUETOOTH_DEVICE_INFO` backing this device, or
+    /// [Error::NotAClassicDevice] if it was only ever discovered over BLE.
+    fn classic_device_info(&self) -> Result<&BLUETOOTH_DEVICE_INFO> {
+        self.device_info
+            .as_ref()
+            .map(|info| &info.0)
+            .ok_or(Error::NotAClassicDevice)
+    }
+
+    /// Authenticates (pairs/bonds) this device, so that its services can
+    /// subsequently be enabled with [Device::set_service_state]. A device
+    /// that was only discovered by [get_bluetooth_devices] but never
+    /// authenticated cannot have its services toggled.
+    pub fn pair(&self, radio: &radio::Radio, method: PairMethod) -> Result<()> {
+        let mut device_info = *self.classic_device_info()?;
+
+        let result = match method {
+            PairMethod::Passkey(passkey) => {
+                let mut passkey: Vec<u16> = passkey.encode_utf16().collect();
+                unsafe {
+                    BluetoothAuthenticateDevice(
+                        HWND::default(),
+                        radio.handle,
+                        &mut device_info,
+                        PWSTR(passkey.as_mut_ptr()),
+                        passkey.len() as u32,
+                    )
+                }
+            }
+            PairMethod::NumericComparison(requirements) => unsafe {
+                BluetoothAuthenticateDeviceEx(
+                    HWND::default(),
+                    radio.handle,
+                    &mut device_info,
+                    None,
+                    requirements,
+                )
+            },
+            PairMethod::OutOfBand(oob_data, requirements) => unsafe {
+                BluetoothAuthenticateDeviceEx(
+                    HWND::default(),
+                    radio.handle,
+                    &mut device_info,
+                    Some(&oob_data),
+                    requirements,
+                )
+            },
+        };
+
+        match WIN32_ERROR(result) {
+            WIN32_ERROR(0) => Ok(()),
+            ERROR_CANCELLED | ERROR_TIMEOUT | WAIT_TIMEOUT => Err(Error::AuthenticationTimeout),
+            error => Err(Error::AuthenticationFailed(error)),
+        }
+    }
+
+    /// Enables or disables a specific service (identified by a GUID) on this
+    /// device, e.g. switching a headset between its A2DP sink and
+    /// Hands-Free roles.
+    ///
+    /// `service_guid` accepts either the canonical
+    /// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` form or the 16-bit Bluetooth
+    /// short form (e.g. `"110B"`), which is expanded against the Bluetooth
+    /// Base UUID.
+    pub fn set_service_state(
+        &self,
+        radio: &radio::Radio,
+        service_guid: &str,
+        mode: Mode,
+    ) -> Result<()> {
+        let guid = parse_service_guid(service_guid)?;
+        let device_info = self.classic_device_info()?;
+        let flags = match mode {
+            Mode::Enable => BLUETOOTH_SERVICE_ENABLE,
+            Mode::Disable => 0,
+        };
+
+        let result = unsafe { BluetoothSetServiceState(radio.handle, device_info, &guid, flags) };
+
+        if result != 0 {
+            return Err(Error::ServiceStateChangeFailed(WIN32_ERROR(result)));
+        }
+
+        Ok(())
     }
 
-    pub fn get_device_services(&self, radio: &radio::Radio) {
+    /// Lists the services this device advertises, regardless of whether
+    /// they come from the classic SDP database or BLE's GATT.
+    pub fn get_device_services(&self, radio: &radio::Radio) -> Result<Vec<Service>> {
+        match self.transport {
+            Transport::Classic => self.classic_services(radio),
+            Transport::LowEnergy => ble::get_gatt_services(&self.address),
+            Transport::Dual => {
+                let mut services = self.classic_services(radio)?;
+                services.extend(ble::get_gatt_services(&self.address)?);
+                Ok(services)
+            }
+        }
+    }
+
+    /// Enumerates services registered in the classic SDP database via
+    /// `BluetoothEnumerateInstalledServices`. Per that API's contract, we
+    /// first query with a null buffer to learn how many service GUIDs there
+    /// are, then allocate a buffer of that size and query again to fill it.
+    fn classic_services(&self, radio: &radio::Radio) -> Result<Vec<Service>> {
+        let device_info = self.classic_device_info()?;
         let mut count: u32 = 0;
-        let mut guid = GUID::zeroed();
-        let service: Option<*mut GUID> = Some(&mut guid);
+        let result = unsafe {
+            BluetoothEnumerateInstalledServices(radio.handle, device_info, &mut count, None)
+        };
+
+        if result != 0 && WIN32_ERROR(result) != ERROR_MORE_DATA {
+            return Err(Error::ServiceEnumerationFailed(WIN32_ERROR(result)));
+        }
 
-        while (unsafe {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut guids = vec![GUID::zeroed(); count as usize];
+        let result = unsafe {
             BluetoothEnumerateInstalledServices(
                 radio.handle,
-                &self.device_info.0,
+                device_info,
                 &mut count,
-                service,
+                Some(guids.as_mut_ptr()),
             )
-        } != 0)
-        {
-            println!("{}, {}, {:?}", self.name, count, service);
+        };
+
+        if result != 0 {
+            return Err(Error::ServiceEnumerationFailed(WIN32_ERROR(result)));
         }
+
+        guids.truncate(count as usize);
+        Ok(guids
+            .into_iter()
+            .map(|guid| Service {
+                name: uuid_name(&guid),
+                guid,
+                characteristics: Vec::new(),
+            })
+            .collect())
+    }
+}
+
+/// Gives a well-known service or characteristic GUID a human-readable name
+/// where we know one, falling back to the GUID itself.
+pub(super) fn uuid_name(guid: &GUID) -> String {
+    let guid_string = format!("{guid:?}").to_uppercase();
+
+    if guid_string.contains("0000110B") {
+        "A2DP Sink".to_string()
+    } else if guid_string.contains("0000111E") {
+        "Hands-Free".to_string()
+    } else {
+        guid_string
     }
 }
 
 #[allow(dead_code)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 /// Represents a Bluetooth address as a vector of bytes. A Bluetooth address is
 /// usually a 48-bit value, but Windows API gives it to us as 6 u8s so this is
 /// how we are dealing with it for now.
@@ -116,6 +354,22 @@ impl Address {
             .collect::<Vec<String>>()
             .join(":")
     }
+
+    /// Packs the address into the `u64` representation the WinRT Bluetooth
+    /// APIs (e.g. `BluetoothLEDevice::FromBluetoothAddressAsync`) expect.
+    pub(super) fn as_u64(&self) -> u64 {
+        self.address
+            .iter()
+            .fold(0u64, |acc, &byte| (acc << 8) | byte as u64)
+    }
+
+    /// Unpacks an [Address] from the `u64` representation the WinRT
+    /// Bluetooth APIs (e.g. `BluetoothLEDevice::BluetoothAddress`) use.
+    pub(super) fn from_u64(address: u64) -> Self {
+        Self {
+            address: address.to_be_bytes()[2..].to_vec(),
+        }
+    }
 }
 
 impl Display for Address {
@@ -153,8 +407,11 @@ impl Into<Device> for BLUETOOTH_DEVICE_INFO {
     /// Converts a [windows::Win32::Devices::Bluetooth::BLUETOOTH_DEVICE_INFO]
     /// to a [Device].
     fn into(self) -> Device {
+        let (class, has_audio_service) = from_class_identifier(self.ulClassofDevice);
         Device {
-            class: from_class_identifier(self.ulClassofDevice),
+            class,
+            has_audio_service,
+            transport: Transport::Classic,
             address: Address::from(self.Address),
             connected: self.fConnected.into(),
             remembered: self.fRemembered.into(),
@@ -162,13 +419,13 @@ impl Into<Device> for BLUETOOTH_DEVICE_INFO {
             name: util::u16_slice_to_string(self.szName.as_slice()),
             last_seen: self.stLastSeen.into(),
             last_connected: self.stLastUsed.into(),
-            device_info: BluetoothDeviceInfo(self.clone()),
+            device_info: Some(BluetoothDeviceInfo(self.clone())),
         }
     }
 }
 
-/// Collects a list of all Bluetooth devices currently known to the system on
-/// all Bluetooth radios available to the system.
+/// Collects a list of all Bluetooth devices currently known to the system,
+/// scoping discovery to the given `radio`.
 ///
 /// # Safety
 ///  
@@ -182,7 +439,7 @@ impl Into<Device> for BLUETOOTH_DEVICE_INFO {
 /// * Finally, the `device_handle` in the
 /// `BluetoothFindDeviceClose` call should always be valid as, if it was not,
 /// we'd have bailed out earlier.
-pub fn get_bluetooth_devices() -> Result<Vec<Device>> {
+pub fn get_bluetooth_devices(radio: &radio::Radio) -> Result<Vec<Device>> {
     let params = BLUETOOTH_DEVICE_SEARCH_PARAMS {
         dwSize: size_of::<BLUETOOTH_DEVICE_SEARCH_PARAMS>() as u32,
         fReturnAuthenticated: BOOL::from(true),
@@ -191,7 +448,7 @@ pub fn get_bluetooth_devices() -> Result<Vec<Device>> {
         fReturnConnected: BOOL::from(true),
         fIssueInquiry: BOOL::from(true),
         cTimeoutMultiplier: 1,
-        hRadio: HANDLE::default(),
+        hRadio: radio.handle,
     };
 
     let mut device_info = BLUETOOTH_DEVICE_INFO::default();
@@ -216,16 +473,79 @@ pub fn get_bluetooth_devices() -> Result<Vec<Device>> {
     Ok(devices)
 }
 
-/// Helper method to convert the class identifier number into the device class. Currently only works for two classes...
+/// Parses a Bluetooth service GUID from either its canonical
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` form or its 16-bit short form
+/// (e.g. `"110B"`), expanding the latter against the Bluetooth Base UUID.
 ///
 /// # Arguments
 ///
-/// * `identifier` - A 32-bit device class identifier as provided by the [spec](https://btprodspecificationrefs.blob.core.windows.net/assigned-numbers/Assigned%20Number%20Types/Assigned_Numbers.pdf)
-fn from_class_identifier(identifier: u32) -> DeviceClass {
-    // TODO: proper matching
-    match identifier {
-        2_360_340 => DeviceClass::Speaker,
-        2_360_344 => DeviceClass::Headphones,
-        _ => DeviceClass::Other,
+/// * `guid` - The GUID string to parse.
+fn parse_service_guid(guid: &str) -> Result<GUID> {
+    let canonical = if guid.len() == 4 && guid.chars().all(|c| c.is_ascii_hexdigit()) {
+        format!("0000{}-{}", guid, BLUETOOTH_BASE_UUID_SUFFIX)
+    } else {
+        guid.to_string()
+    };
+
+    let parts: Vec<&str> = canonical.splitn(5, '-').collect();
+    let [data1, data2, data3, data4_hi, data4_lo]: [&str; 5] = parts
+        .try_into()
+        .map_err(|_| Error::InvalidServiceGuid(guid.to_string()))?;
+
+    let invalid = || Error::InvalidServiceGuid(guid.to_string());
+
+    let data1 = u32::from_str_radix(data1, 16).map_err(|_| invalid())?;
+    let data2 = u16::from_str_radix(data2, 16).map_err(|_| invalid())?;
+    let data3 = u16::from_str_radix(data3, 16).map_err(|_| invalid())?;
+    let data4_hi = u16::from_str_radix(data4_hi, 16).map_err(|_| invalid())?;
+    let data4_lo = u64::from_str_radix(data4_lo, 16).map_err(|_| invalid())?;
+
+    let mut data4 = [0u8; 8];
+    data4[0] = (data4_hi >> 8) as u8;
+    data4[1] = (data4_hi & 0xFF) as u8;
+    for (i, byte) in data4.iter_mut().skip(2).enumerate() {
+        *byte = ((data4_lo >> (8 * (5 - i))) & 0xFF) as u8;
     }
+
+    Ok(GUID::from_values(data1, data2, data3, data4))
+}
+
+/// The Audio service bit of the 11-bit Major Service Class bitmask (bit 21
+/// of the whole Class of Device value), as defined by the [Class of Device
+/// spec](https://btprodspecificationrefs.blob.core.windows.net/assigned-numbers/Assigned%20Number%20Types/Assigned_Numbers.pdf).
+const MAJOR_SERVICE_CLASS_AUDIO: u32 = 1 << 21;
+
+/// Audio/Video Major Device Class, as defined by the Class of Device spec.
+const MAJOR_DEVICE_CLASS_AUDIO_VIDEO: u32 = 0x04;
+
+/// Decodes the Class of Device (CoD) value into a [DeviceClass] and whether
+/// the device advertises the Audio major service class.
+///
+/// The 24-bit CoD value is laid out as: bits 0-1 are the format (always
+/// `00`), bits 2-7 are the Minor Device Class, bits 8-12 are the Major
+/// Device Class, and bits 13-23 are the Major Service Class bitmask.
+///
+/// # Arguments
+///
+/// * `identifier` - A 32-bit device class identifier as provided by the [spec](https://btprodspecificationrefs.blob.core.windows.net/assigned-numbers/Assigned%20Number%20Types/Assigned_Numbers.pdf)
+fn from_class_identifier(identifier: u32) -> (DeviceClass, bool) {
+    let major_device_class = (identifier >> 8) & 0x1F;
+    let minor_device_class = (identifier >> 2) & 0x3F;
+    let has_audio_service = identifier & MAJOR_SERVICE_CLASS_AUDIO != 0;
+
+    let class = if major_device_class == MAJOR_DEVICE_CLASS_AUDIO_VIDEO {
+        match minor_device_class {
+            0x01 => DeviceClass::Headset,
+            0x02 => DeviceClass::HandsFree,
+            0x04 => DeviceClass::Microphone,
+            0x05 => DeviceClass::Speaker,
+            0x06 => DeviceClass::Headphones,
+            0x07 | 0x08 => DeviceClass::PortableAudio,
+            _ => DeviceClass::Other,
+        }
+    } else {
+        DeviceClass::Other
+    };
+
+    (class, has_audio_service)
 }
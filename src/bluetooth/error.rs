@@ -1,5 +1,7 @@
 //! Enumerates common errors for this module.
 
+use windows::Win32::Foundation::WIN32_ERROR;
+
 #[derive(Debug)]
 /// Enumerates common errors for this module.
 pub enum Error {
@@ -7,4 +9,38 @@ pub enum Error {
     NoDevicesFound,
     /// Returned if there are no Bluetooth radios available to the system.
     NoRadiosFound,
+    /// Returned if a service GUID string could not be parsed. Holds the
+    /// offending input.
+    InvalidServiceGuid(String),
+    /// Returned if `BluetoothSetServiceState` failed to enable or disable a
+    /// service. Holds the underlying Win32 error code.
+    ServiceStateChangeFailed(WIN32_ERROR),
+    /// Returned if pairing/authentication with a device failed. Holds the
+    /// underlying Win32 error code.
+    AuthenticationFailed(WIN32_ERROR),
+    /// Returned if pairing/authentication with a device timed out or was
+    /// cancelled by the user.
+    AuthenticationTimeout,
+    /// Returned if enumerating a device's services failed. Holds the
+    /// underlying Win32 error code.
+    ServiceEnumerationFailed(WIN32_ERROR),
+    /// Returned if a BLE device could not be reached via the WinRT
+    /// Bluetooth APIs (e.g. it has gone out of range).
+    LowEnergyDeviceNotFound,
+    /// Returned if `BluetoothEnableDiscovery` failed to change a radio's
+    /// discoverability. Holds the underlying Win32 error code.
+    DiscoverabilityChangeFailed(WIN32_ERROR),
+    /// Returned if `BluetoothEnableIncomingConnections` failed to change
+    /// whether a radio accepts incoming connections. Holds the underlying
+    /// Win32 error code.
+    IncomingConnectionsChangeFailed(WIN32_ERROR),
+    /// Returned if an operation is not supported by the radio's driver, or
+    /// (for power state and naming) if no matching WinRT radio could be
+    /// found to carry it out.
+    OperationNotSupported,
+    /// Returned when a classic-only operation (pairing, enabling a classic
+    /// service, enumerating classic SDP services) is attempted on a device
+    /// that was only ever discovered over BLE and has no backing
+    /// `BLUETOOTH_DEVICE_INFO`.
+    NotAClassicDevice,
 }
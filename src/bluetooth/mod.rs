@@ -1,10 +1,12 @@
 //! List available Bluetooth devices and connect to them. It supports one
 //! Bluetooth radio per system.
 
+pub mod ble;
 pub mod device;
 pub mod error;
 pub mod radio;
 pub mod util;
+pub mod watcher;
 
 use std::ops::Deref;
 
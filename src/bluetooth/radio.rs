@@ -3,19 +3,30 @@
 use std::fmt::Debug;
 use std::mem::size_of;
 
+use windows::Devices::Radios::{Radio as WinRtRadio, RadioKind, RadioState};
 use windows::Win32::Devices::Bluetooth::{
-    BluetoothFindFirstRadio, BluetoothFindRadioClose, BluetoothGetRadioInfo,
-    BLUETOOTH_FIND_RADIO_PARAMS, BLUETOOTH_RADIO_INFO,
+    BluetoothEnableDiscovery, BluetoothEnableIncomingConnections, BluetoothFindFirstRadio,
+    BluetoothFindNextRadio, BluetoothFindRadioClose, BluetoothGetRadioInfo, BluetoothIsConnectable,
+    BluetoothIsDiscoverable, BLUETOOTH_FIND_RADIO_PARAMS, BLUETOOTH_RADIO_INFO,
 };
-use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Foundation::{CloseHandle, GetLastError, BOOL, HANDLE};
 
+use super::device::Address;
 use super::error::Error;
+use super::watcher::DeviceWatcher;
 use super::{util, Result};
 
 /// Represents a Bluetooth radio connected to the system.
 #[derive(Debug)]
 pub struct Radio {
     pub name: String,
+    pub address: Address,
+    /// The radio's own Class of Device value, as advertised to other
+    /// Bluetooth devices. This is the same 24-bit bitfield decoded for
+    /// discovered devices (format, Minor/Major Device Class, Major Service
+    /// Class bitmask).
+    pub class_of_device: u32,
+    pub manufacturer: u16,
     pub handle: HANDLE,
 }
 
@@ -25,41 +36,176 @@ impl Drop for Radio {
     }
 }
 
-/// Gets the first Bluetooth radio plugged into the system.
+impl Radio {
+    /// Starts a continuous, background discovery scan scoped to this radio.
+    /// Rather than a single [crate::bluetooth::device::get_bluetooth_devices]
+    /// snapshot, the returned [DeviceWatcher] repeatedly re-scans and emits
+    /// add/update/remove events as devices come and go, so the crate can be
+    /// used as a live tray app rather than a one-shot enumeration tool.
+    pub fn watch_devices(&self) -> DeviceWatcher<'_> {
+        DeviceWatcher::start(self)
+    }
+
+    /// Reports whether this radio is currently powered on.
+    ///
+    /// Classic Win32 Bluetooth APIs have no notion of radio power, so this
+    /// is backed by the WinRT [windows::Devices::Radios::Radio] instead.
+    pub fn is_powered(&self) -> Result<bool> {
+        let state = self
+            .winrt_radio()?
+            .State()
+            .map_err(|_| Error::OperationNotSupported)?;
+
+        Ok(state == RadioState::On)
+    }
+
+    /// Powers this radio on or off.
+    ///
+    /// Classic Win32 Bluetooth APIs have no notion of radio power, so this
+    /// is backed by the WinRT [windows::Devices::Radios::Radio] instead. A
+    /// radio should be powered on before its discoverability, connectivity
+    /// or a device's services are changed - those calls otherwise fail.
+    pub fn set_powered(&self, enabled: bool) -> Result<()> {
+        let state = if enabled {
+            RadioState::On
+        } else {
+            RadioState::Off
+        };
+
+        self.winrt_radio()?
+            .SetStateAsync(state)
+            .and_then(|operation| operation.get())
+            .map_err(|_| Error::OperationNotSupported)?;
+
+        Ok(())
+    }
+
+    /// Reports whether this radio is discoverable by other Bluetooth
+    /// devices.
+    pub fn is_discoverable(&self) -> bool {
+        unsafe { BluetoothIsDiscoverable(self.handle) }.as_bool()
+    }
+
+    /// Makes this radio discoverable, or undiscoverable, by other Bluetooth
+    /// devices.
+    pub fn set_discoverable(&self, enabled: bool) -> Result<()> {
+        unsafe { BluetoothEnableDiscovery(self.handle, BOOL::from(enabled)) };
+        let last_error = unsafe { GetLastError() };
+
+        if self.is_discoverable() != enabled {
+            return Err(Error::DiscoverabilityChangeFailed(last_error));
+        }
+
+        Ok(())
+    }
+
+    /// Reports whether this radio currently accepts incoming connections.
+    pub fn is_connectable(&self) -> bool {
+        unsafe { BluetoothIsConnectable(self.handle) }.as_bool()
+    }
+
+    /// Enables or disables incoming connections to this radio.
+    pub fn set_connectable(&self, enabled: bool) -> Result<()> {
+        unsafe { BluetoothEnableIncomingConnections(self.handle, BOOL::from(enabled)) };
+        let last_error = unsafe { GetLastError() };
+
+        if self.is_connectable() != enabled {
+            return Err(Error::IncomingConnectionsChangeFailed(last_error));
+        }
+
+        Ok(())
+    }
+
+    /// Sets this radio's friendly name, if the driver supports it.
+    ///
+    /// Neither the classic Win32 Bluetooth API nor WinRT's
+    /// [windows::Devices::Radios::Radio] expose a way to rename the local
+    /// adapter, so this always reports
+    /// [Error::OperationNotSupported](super::error::Error::OperationNotSupported)
+    /// today. It is kept as part of the API so a driver-specific
+    /// implementation can be dropped in later without a breaking change.
+    pub fn set_name(&self, _name: &str) -> Result<()> {
+        Err(Error::OperationNotSupported)
+    }
+
+    /// Finds the WinRT [windows::Devices::Radios::Radio] corresponding to
+    /// this Bluetooth radio. There can only be one Bluetooth radio exposed
+    /// through this API at a time, so the first one found is assumed to be
+    /// this radio.
+    fn winrt_radio(&self) -> Result<WinRtRadio> {
+        let radios = WinRtRadio::GetRadiosAsync()
+            .and_then(|operation| operation.get())
+            .map_err(|_| Error::OperationNotSupported)?;
+
+        radios
+            .into_iter()
+            .find(|radio| radio.Kind() == Ok(RadioKind::Bluetooth))
+            .ok_or(Error::OperationNotSupported)
+    }
+}
+
+/// Gets the first Bluetooth radio plugged into the system. Kept around for
+/// callers that only care about a single radio; see
+/// [get_bluetooth_radios] for systems with more than one (e.g. a dock/USB
+/// dongle plus an internal adapter).
+pub fn get_bluetooth_radio() -> Result<Radio> {
+    get_bluetooth_radios()?
+        .into_iter()
+        .next()
+        .ok_or(Error::NoRadiosFound)
+}
+
+/// Gets every Bluetooth radio plugged into the system.
 ///
 /// According to [Microsoft's own
 /// documentation](https://learn.microsoft.com/en-us/windows-hardware/drivers/bluetooth/bluetooth-faq),
-/// "The Bluetooth stack in Windows supports only one Bluetooth radio".
-/// This is the use case that this tool is targeting anyway. But in
-/// case someone really insists on adding more than one radio, they should
-/// amend this function.
+/// "The Bluetooth stack in Windows supports only one Bluetooth radio", but
+/// in practice machines with a dock/USB dongle plus an internal adapter do
+/// expose more than one, so we enumerate all of them here.
 ///
 /// # Safety
 ///
 /// This method calls the Win32 C API and, therefore, contains several
 /// `unsafe` blocks. We need to take care that the
 /// [windows::Win32::Devices::Bluetooth::BLUETOOTH_FIND_RADIO_PARAMS]
-/// struct is initialized and valid, and that the handle returned is
+/// struct is initialized and valid, and that every handle returned is
 /// properly closed at the end.
-pub fn get_bluetooth_radio() -> Result<Radio> {
+pub fn get_bluetooth_radios() -> Result<Vec<Radio>> {
     let find_params = BLUETOOTH_FIND_RADIO_PARAMS {
         dwSize: size_of::<BLUETOOTH_FIND_RADIO_PARAMS>() as u32,
     };
 
-    let mut radio_info = BLUETOOTH_RADIO_INFO::default();
-    radio_info.dwSize = size_of::<BLUETOOTH_RADIO_INFO>() as u32;
-
     let mut radio = HANDLE::default();
     let find_handle = unsafe { BluetoothFindFirstRadio(&find_params, &mut radio) };
     if find_handle == 0 {
         return Err(Error::NoRadiosFound);
     }
 
-    unsafe { BluetoothGetRadioInfo(radio, &mut radio_info) };
+    let mut radios = Vec::new();
+    radios.push(radio_info(radio)?);
+
+    while unsafe { BluetoothFindNextRadio(find_handle, &mut radio) } == BOOL::from(true) {
+        radios.push(radio_info(radio)?);
+    }
+
     unsafe { BluetoothFindRadioClose(find_handle) };
 
+    Ok(radios)
+}
+
+/// Queries a radio handle for its [BLUETOOTH_RADIO_INFO] and wraps it as a
+/// [Radio].
+fn radio_info(handle: HANDLE) -> Result<Radio> {
+    let mut radio_info = BLUETOOTH_RADIO_INFO::default();
+    radio_info.dwSize = size_of::<BLUETOOTH_RADIO_INFO>() as u32;
+
+    unsafe { BluetoothGetRadioInfo(handle, &mut radio_info) };
+
     Ok(Radio {
-        handle: radio,
+        handle,
         name: util::u16_slice_to_string(radio_info.szName.as_slice()),
+        address: Address::from(radio_info.address),
+        class_of_device: radio_info.ulClassofDevice,
+        manufacturer: radio_info.manufacturer,
     })
 }
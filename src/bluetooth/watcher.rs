@@ -0,0 +1,163 @@
+//! Continuous Bluetooth device discovery, driven from a background thread.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use windows::Win32::Devices::Bluetooth::{
+    BluetoothFindDeviceClose, BluetoothFindFirstDevice, BluetoothFindNextDevice,
+    BLUETOOTH_DEVICE_INFO, BLUETOOTH_DEVICE_SEARCH_PARAMS,
+};
+use windows::Win32::Foundation::{BOOL, HANDLE};
+
+use super::device::{Address, Device};
+use super::radio::Radio;
+
+/// How long to wait between successive inquiry scans.
+const SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// An event emitted by a [DeviceWatcher] as it diffs successive scans.
+#[derive(Debug)]
+pub enum DeviceEvent {
+    /// A device was seen for the first time.
+    Added(Device),
+    /// A previously seen device was seen again. `connected_changed` is set
+    /// if its `connected` flag flipped since the last scan, so a UI can
+    /// react when a headset powers on or off.
+    Updated {
+        device: Device,
+        connected_changed: bool,
+    },
+    /// A previously seen device was not returned by the latest scan.
+    Removed(Address),
+}
+
+/// Drives repeated Bluetooth inquiries on a background thread and emits
+/// [DeviceEvent]s over a channel as devices come and go, diffed by
+/// [Address]. Call [DeviceWatcher::stop], or just let it drop, to join the
+/// thread and close the radio-scoped handles cleanly.
+///
+/// Borrows the [Radio] it was started from for its whole lifetime, so the
+/// radio (and its handle) can't be dropped out from under the background
+/// thread while it's still scanning.
+pub struct DeviceWatcher<'radio> {
+    /// Receives device events as they are discovered. Poll this from your
+    /// event loop, e.g. with [Receiver::try_recv] or [Receiver::recv].
+    pub events: Receiver<DeviceEvent>,
+    stop_flag: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    _radio: PhantomData<&'radio Radio>,
+}
+
+impl<'radio> DeviceWatcher<'radio> {
+    /// Starts watching `radio` for device changes, scoping inquiries to it.
+    pub(super) fn start(radio: &'radio Radio) -> Self {
+        let radio_handle = radio.handle;
+        let (sender, events) = mpsc::channel();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let thread_stop_flag = Arc::clone(&stop_flag);
+
+        let thread = thread::spawn(move || {
+            let mut known: HashMap<Address, bool> = HashMap::new();
+
+            while !thread_stop_flag.load(Ordering::Relaxed) {
+                let mut seen: HashMap<Address, bool> = HashMap::new();
+
+                for device in scan(radio_handle) {
+                    let address = device.address.clone();
+                    let connected = device.connected;
+                    seen.insert(address.clone(), connected);
+
+                    let event = match known.get(&address) {
+                        None => DeviceEvent::Added(device),
+                        Some(previously_connected) => DeviceEvent::Updated {
+                            connected_changed: *previously_connected != connected,
+                            device,
+                        },
+                    };
+
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+
+                for address in known.keys() {
+                    if !seen.contains_key(address) && sender.send(DeviceEvent::Removed(address.clone())).is_err() {
+                        return;
+                    }
+                }
+
+                known = seen;
+
+                if thread_stop_flag.load(Ordering::Relaxed) {
+                    break;
+                }
+                thread::sleep(SCAN_INTERVAL);
+            }
+        });
+
+        Self {
+            events,
+            stop_flag,
+            thread: Some(thread),
+            _radio: PhantomData,
+        }
+    }
+
+    /// Stops the background scan and joins its thread. Equivalent to
+    /// dropping the [DeviceWatcher], but explicit at the call site.
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl<'radio> Drop for DeviceWatcher<'radio> {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Runs a single inquiry scan on `radio_handle` and collects the result.
+/// Unlike [super::device::get_bluetooth_devices], an empty result is not an
+/// error here - it just means no devices were seen this round.
+fn scan(radio_handle: HANDLE) -> Vec<Device> {
+    let params = BLUETOOTH_DEVICE_SEARCH_PARAMS {
+        dwSize: size_of::<BLUETOOTH_DEVICE_SEARCH_PARAMS>() as u32,
+        fReturnAuthenticated: BOOL::from(true),
+        fReturnRemembered: BOOL::from(true),
+        fReturnUnknown: BOOL::from(true),
+        fReturnConnected: BOOL::from(true),
+        fIssueInquiry: BOOL::from(true),
+        cTimeoutMultiplier: 1,
+        hRadio: radio_handle,
+    };
+
+    let mut device_info = BLUETOOTH_DEVICE_INFO::default();
+    device_info.dwSize = size_of::<BLUETOOTH_DEVICE_INFO>() as u32;
+
+    let device_handle = unsafe { BluetoothFindFirstDevice(&params, &mut device_info) };
+    if device_handle == 0 {
+        return Vec::new();
+    }
+
+    let mut devices: Vec<Device> = Vec::new();
+    devices.push(device_info.into());
+
+    while unsafe { BluetoothFindNextDevice(device_handle, &mut device_info) == BOOL::from(true) } {
+        devices.push(device_info.into());
+    }
+
+    unsafe {
+        BluetoothFindDeviceClose(device_handle);
+    }
+
+    devices
+}
@@ -1,14 +1,21 @@
 mod bluetooth;
 
+use crate::bluetooth::ble;
 use crate::bluetooth::device::get_bluetooth_devices;
 use crate::bluetooth::radio::get_bluetooth_radio;
 use crate::bluetooth::Result;
 
 fn main() -> Result<()> {
     let radio = get_bluetooth_radio()?;
-    let devices = get_bluetooth_devices()?;
+
+    let mut devices = get_bluetooth_devices(&radio)?;
+    devices.extend(ble::discover_devices()?);
+
     for device in devices {
-        device.get_device_services(&radio);
+        let services = device.get_device_services(&radio)?;
+        for service in services {
+            println!("{}: {}", device.name, service.name);
+        }
     }
 
     Ok(())